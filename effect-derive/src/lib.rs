@@ -0,0 +1,298 @@
+//! Companion proc-macro crate for [`effect_cell`], providing
+//! [`#[derive(Effects)]`](macro@Effects).
+
+use proc_macro::TokenStream;
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, Data, DeriveInput, Fields};
+
+/// Derives a struct-wide effect wrapper for a struct with named fields.
+///
+/// Applied to `struct Foo { .. }`, this generates a `FooEffects` wrapper that
+/// owns a `Foo` and, for every field, exposes update-aware accessors mirroring
+/// [`EffectCell`](effect_cell::EffectCell): `update_<field>` /
+/// `update_<field>_lambda` / `set_<field>` and a per-field `bind_<field>` taking
+/// an `FnMut(&FieldTy)`. Mutating a field runs the effects bound to that field,
+/// and — when the field's value actually changed (compared via [`PartialEq`]) —
+/// also runs the aggregate effects bound with `bind`, which receive `&Foo`.
+///
+/// Fields whose type is not `PartialEq` simply skip change-detection and always
+/// run the aggregate. This is decided per-field by autoref specialization, so
+/// it also falls out correctly for a field whose type depends on one of the
+/// struct's own generic parameters: the comparison type-checks (and dedups)
+/// when the field type actually is `PartialEq` in that generic context — e.g.
+/// a bare `a: T` field on `struct Pair<T: PartialEq>`, since the bound is part
+/// of the generated wrapper's own generics — and otherwise, including an
+/// unbounded parameter or a compound type like `Cell<T>` that isn't
+/// `PartialEq` even when `T` is, falls back to always running the aggregate.
+///
+/// # Examples
+///
+/// ```
+/// use std::cell::Cell;
+/// use std::rc::Rc;
+/// use effect_derive::Effects;
+///
+/// #[derive(Effects)]
+/// struct Point {
+///     x: i32,
+///     y: i32,
+/// }
+///
+/// let changes = Rc::new(Cell::new(0));
+/// let mut point = PointEffects::new(Point { x: 0, y: 0 });
+/// let c = changes.clone();
+/// point.bind(move |_| c.set(c.get() + 1));
+///
+/// point.update_x(3); // x changed, aggregate fires
+/// point.update_x(3); // idempotent, aggregate skipped
+/// assert_eq!(point.get().x, 3);
+/// assert_eq!(changes.get(), 1);
+/// ```
+///
+/// A field whose type is not `PartialEq` still works; its updates always fire
+/// the aggregate:
+///
+/// ```
+/// use effect_derive::Effects;
+///
+/// struct NotEq(i32);
+///
+/// #[derive(Effects)]
+/// struct Holder {
+///     inner: NotEq,
+/// }
+///
+/// let mut holder = HolderEffects::new(Holder { inner: NotEq(0) });
+/// holder.update_inner(NotEq(1));
+/// assert_eq!(holder.into_inner().inner.0, 1);
+/// ```
+///
+/// A generic field bounded `PartialEq` on the struct dedups just like a
+/// concrete one; left unbounded, it can't be proven `PartialEq` inside the
+/// generic wrapper and always fires the aggregate, even when instantiated
+/// with a type (like `i32`) that does implement it:
+///
+/// ```
+/// use std::cell::Cell;
+/// use std::rc::Rc;
+/// use effect_derive::Effects;
+///
+/// #[derive(Effects)]
+/// struct Bounded<T: PartialEq> {
+///     a: T,
+/// }
+///
+/// let changes = Rc::new(Cell::new(0));
+/// let mut bounded = BoundedEffects::new(Bounded { a: 0i32 });
+/// let c = changes.clone();
+/// bounded.bind(move |_| c.set(c.get() + 1));
+/// bounded.update_a(1);
+/// bounded.update_a(1); // idempotent, aggregate skipped
+/// assert_eq!(changes.get(), 1);
+///
+/// #[derive(Effects)]
+/// struct Unbounded<T> {
+///     a: T,
+/// }
+///
+/// let changes = Rc::new(Cell::new(0));
+/// let mut unbounded = UnboundedEffects::new(Unbounded { a: 0i32 });
+/// let c = changes.clone();
+/// unbounded.bind(move |_| c.set(c.get() + 1));
+/// unbounded.update_a(1);
+/// unbounded.update_a(1); // can't prove `PartialEq`, aggregate fires anyway
+/// assert_eq!(changes.get(), 2);
+/// ```
+///
+/// A `PartialEq`-bounded generic parameter doesn't make every field built out
+/// of it `PartialEq` — a field like `Cell<T>` still isn't, so it compiles
+/// (no bare `!=` is ever emitted) and, like any non-`PartialEq` field, always
+/// fires the aggregate:
+///
+/// ```
+/// use std::cell::Cell;
+/// use std::rc::Rc;
+/// use effect_derive::Effects;
+///
+/// #[derive(Effects)]
+/// struct Wrapped<T: PartialEq> {
+///     a: Cell<T>,
+/// }
+///
+/// let changes = Rc::new(Cell::new(0));
+/// let mut wrapped = WrappedEffects::new(Wrapped { a: Cell::new(0i32) });
+/// let c = changes.clone();
+/// wrapped.bind(move |_| c.set(c.get() + 1));
+/// wrapped.update_a(Cell::new(0));
+/// wrapped.update_a(Cell::new(0)); // `Cell<T>` isn't `PartialEq` under `T: PartialEq` alone, aggregate fires anyway
+/// assert_eq!(changes.get(), 2);
+/// ```
+#[proc_macro_derive(Effects)]
+pub fn derive_effects(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let name = &input.ident;
+    let wrapper = format_ident!("{}Effects", name);
+
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(named) => &named.named,
+            _ => {
+                return syn::Error::new_spanned(
+                    name,
+                    "`Effects` can only be derived for structs with named fields",
+                )
+                .to_compile_error()
+                .into();
+            }
+        },
+        _ => {
+            return syn::Error::new_spanned(name, "`Effects` can only be derived for structs")
+                .to_compile_error()
+                .into();
+        }
+    };
+
+    let generics = &input.generics;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let support = format_ident!("__{}_effects_support", name);
+
+    let mut storage = Vec::new();
+    let mut init = Vec::new();
+    let mut methods = Vec::new();
+
+    for field in fields {
+        let field_name = field.ident.as_ref().expect("named field");
+        let field_ty = &field.ty;
+        let effects_field = format_ident!("{}_effects", field_name);
+        let bind_fn = format_ident!("bind_{}", field_name);
+        let update_fn = format_ident!("update_{}", field_name);
+        let update_lambda_fn = format_ident!("update_{}_lambda", field_name);
+        let set_fn = format_ident!("set_{}", field_name);
+
+        storage.push(quote! {
+            #effects_field: ::std::vec::Vec<::std::boxed::Box<dyn FnMut(&#field_ty)>>
+        });
+        init.push(quote! { #effects_field: ::std::vec::Vec::new() });
+
+        methods.push(quote! {
+            /// Binds an effect that runs whenever this field is updated.
+            pub fn #bind_fn<F: FnMut(&#field_ty) + 'static>(&mut self, effect: F) {
+                self.#effects_field.push(::std::boxed::Box::new(effect));
+            }
+
+            /// Replaces the field's value, runs the field's effects, and runs the
+            /// aggregate effects if the value changed.
+            ///
+            /// Change-detection uses the field type's [`PartialEq`] when it has
+            /// one (autoref specialization resolves this per-field, including
+            /// for a generic field whose concrete type is `PartialEq` in this
+            /// generic context); otherwise it skips the comparison and always
+            /// runs the aggregate.
+            pub fn #update_fn(&mut self, new: #field_ty) {
+                let changed = {
+                    use #support::{ChangedViaEq as _, ChangedViaFallback as _};
+                    (&#support::Probe(&self.inner.#field_name, &new)).__effects_changed()
+                };
+                self.inner.#field_name = new;
+                for f in &mut self.#effects_field {
+                    f(&self.inner.#field_name);
+                }
+                if changed {
+                    self.run_aggregate();
+                }
+            }
+
+            /// Mutates the field in place, runs the field's effects, then runs
+            /// the aggregate effects.
+            ///
+            /// Unlike the value-replacing `update_<field>`, the in-place path
+            /// cannot snapshot the prior value without a `Clone` bound, so the
+            /// aggregate always fires here regardless of whether the value
+            /// changed. Use the value-replacing accessor when change-detection is
+            /// required.
+            pub fn #update_lambda_fn<F: FnMut(&mut #field_ty) + 'static>(&mut self, mut lambda: F) {
+                lambda(&mut self.inner.#field_name);
+                for f in &mut self.#effects_field {
+                    f(&self.inner.#field_name);
+                }
+                self.run_aggregate();
+            }
+
+            /// Replaces the field's value without running any effects.
+            pub fn #set_fn(&mut self, new: #field_ty) {
+                self.inner.#field_name = new;
+            }
+        });
+    }
+
+    let expanded = quote! {
+        /// Autoref-specialization helper: change-detection uses `PartialEq` when
+        /// the field type has it, and otherwise reports "changed" unconditionally.
+        #[doc(hidden)]
+        #[allow(non_snake_case)]
+        mod #support {
+            pub struct Probe<'a, T>(pub &'a T, pub &'a T);
+
+            pub trait ChangedViaEq {
+                fn __effects_changed(&self) -> bool;
+            }
+            impl<T: ::core::cmp::PartialEq> ChangedViaEq for Probe<'_, T> {
+                fn __effects_changed(&self) -> bool {
+                    self.0 != self.1
+                }
+            }
+
+            pub trait ChangedViaFallback {
+                fn __effects_changed(&self) -> bool;
+            }
+            impl<T> ChangedViaFallback for &Probe<'_, T> {
+                fn __effects_changed(&self) -> bool {
+                    true
+                }
+            }
+        }
+
+        /// Struct-wide effect wrapper generated by `#[derive(Effects)]`.
+        pub struct #wrapper #generics #where_clause {
+            inner: #name #ty_generics,
+            aggregate: ::std::vec::Vec<::std::boxed::Box<dyn FnMut(&#name #ty_generics)>>,
+            #(#storage,)*
+        }
+
+        impl #impl_generics #wrapper #ty_generics #where_clause {
+            /// Wraps `inner`, with no effects bound.
+            pub fn new(inner: #name #ty_generics) -> Self {
+                Self {
+                    inner,
+                    aggregate: ::std::vec::Vec::new(),
+                    #(#init,)*
+                }
+            }
+
+            /// Returns the wrapped value, dropping all bound effects.
+            pub fn into_inner(self) -> #name #ty_generics {
+                self.inner
+            }
+
+            /// Returns a reference to the wrapped value.
+            pub fn get(&self) -> &#name #ty_generics {
+                &self.inner
+            }
+
+            /// Binds an aggregate effect that runs whenever any field changes.
+            pub fn bind<F: FnMut(&#name #ty_generics) + 'static>(&mut self, effect: F) {
+                self.aggregate.push(::std::boxed::Box::new(effect));
+            }
+
+            fn run_aggregate(&mut self) {
+                for f in &mut self.aggregate {
+                    f(&self.inner);
+                }
+            }
+
+            #(#methods)*
+        }
+    };
+
+    expanded.into()
+}
@@ -1,10 +1,143 @@
 #![doc = include_str!("../README.md")]
+use core::any::Any;
 use core::fmt::Debug;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+/// Derives a struct-wide effect wrapper exposing each field as update-aware.
+/// See the [`effect_derive`] crate for details.
+pub use effect_derive::Effects;
 use core::ops::{
     AddAssign, BitAndAssign, BitOrAssign, BitXorAssign, DivAssign, MulAssign, RemAssign, ShlAssign,
     ShrAssign, SubAssign,
 };
 
+/// An opaque handle to an effect bound to an [`EffectCell`].
+///
+/// Returned by [`EffectCell::bind`] and used to [`unbind`](EffectCell::unbind) or
+/// [`rebind`](EffectCell::rebind) the effect later. The embedded generation is
+/// validated against the slot on every access, so a key left over from an
+/// already-removed effect can never address an effect later bound into the same
+/// recycled slot.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct EffectKey {
+    index: usize,
+    generation: u32,
+}
+
+/// A boxed effect closure, as bound by [`EffectCell::bind`].
+type Effect<T> = Box<dyn FnMut(&T)>;
+
+struct Slot<T> {
+    generation: u32,
+    priority: i32,
+    seq: u64,
+    effect: Option<Effect<T>>,
+}
+
+/// A generational-index slot map of effects shared by the effect cells.
+///
+/// Empty slots are recycled through a free list; each removal bumps the slot's
+/// generation so stale [`EffectKey`]s are rejected (ABA safety). Every insert
+/// stamps the slot with a monotonic sequence number so iteration order follows
+/// true insertion order even after slots are recycled.
+struct SlotMap<T> {
+    slots: Vec<Slot<T>>,
+    free: Vec<usize>,
+    next_seq: u64,
+}
+
+impl<T> SlotMap<T> {
+    fn new() -> Self {
+        Self {
+            slots: Vec::new(),
+            free: Vec::new(),
+            next_seq: 0,
+        }
+    }
+
+    fn insert(&mut self, effect: Effect<T>) -> EffectKey {
+        self.insert_with_priority(effect, 0)
+    }
+
+    fn insert_with_priority(&mut self, effect: Effect<T>, priority: i32) -> EffectKey {
+        let seq = self.next_seq;
+        self.next_seq += 1;
+        if let Some(index) = self.free.pop() {
+            let slot = &mut self.slots[index];
+            slot.priority = priority;
+            slot.seq = seq;
+            slot.effect = Some(effect);
+            EffectKey {
+                index,
+                generation: slot.generation,
+            }
+        } else {
+            let index = self.slots.len();
+            self.slots.push(Slot {
+                generation: 0,
+                priority,
+                seq,
+                effect: Some(effect),
+            });
+            EffectKey {
+                index,
+                generation: 0,
+            }
+        }
+    }
+
+    fn remove(&mut self, key: EffectKey) -> bool {
+        match self.slots.get_mut(key.index) {
+            Some(slot) if slot.generation == key.generation && slot.effect.is_some() => {
+                slot.effect = None;
+                slot.generation = slot.generation.wrapping_add(1);
+                self.free.push(key.index);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn replace(&mut self, key: EffectKey, effect: Effect<T>) -> bool {
+        match self.slots.get_mut(key.index) {
+            Some(slot) if slot.generation == key.generation && slot.effect.is_some() => {
+                slot.effect = Some(effect);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Runs occupied effects in insertion order, as recorded by each slot's
+    /// sequence number, so recycling a freed slot does not reorder the effects.
+    fn call(&mut self, data: &T) {
+        let mut order: Vec<usize> = (0..self.slots.len())
+            .filter(|&i| self.slots[i].effect.is_some())
+            .collect();
+        order.sort_by_key(|&i| self.slots[i].seq);
+        for i in order {
+            if let Some(f) = self.slots[i].effect.as_mut() {
+                f(data);
+            }
+        }
+    }
+
+    /// Runs occupied effects in ascending priority order, breaking ties by
+    /// insertion order (sequence number).
+    fn call_sorted(&mut self, data: &T) {
+        let mut order: Vec<usize> = (0..self.slots.len())
+            .filter(|&i| self.slots[i].effect.is_some())
+            .collect();
+        order.sort_by_key(|&i| (self.slots[i].priority, self.slots[i].seq));
+        for i in order {
+            if let Some(f) = self.slots[i].effect.as_mut() {
+                f(data);
+            }
+        }
+    }
+}
+
 /// A container that runs one or many effects on data mutation.
 /// The effect is run after data is updated as per the conventions of the Observer data structure.
 ///
@@ -19,7 +152,30 @@ use core::ops::{
 /// ```
 pub struct EffectCell<T> {
     data: T,
-    effects: Vec<Box<dyn FnMut(&T)>>,
+    effects: SlotMap<T>,
+    dedup: Dedup<T>,
+}
+
+/// Controls whether a mutation runs the bound effects, or only runs them when
+/// the stored value actually changed.
+///
+/// `Custom` carries a closure that owns the cached baseline (a snapshot of the
+/// previous value, or its 64-bit fingerprint) and returns whether the value
+/// changed, updating its baseline as a side effect. Keeping the baseline inside
+/// the closure lets [`EffectCell`] stay generic without a blanket `Clone`/`Hash`
+/// bound — only the relevant constructor imposes one.
+enum Dedup<T> {
+    /// Effects always run on mutation (the default).
+    Always,
+    /// Effects run only when the change-detection closure reports a change.
+    Custom(Box<dyn FnMut(&T) -> bool>),
+}
+
+/// Computes a 64-bit fingerprint of `value` via [`DefaultHasher`].
+fn fingerprint<T: Hash>(value: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
 }
 
 impl<T> EffectCell<T> {
@@ -27,7 +183,79 @@ impl<T> EffectCell<T> {
     pub fn new(data: T) -> Self {
         Self {
             data,
-            effects: Vec::new(),
+            effects: SlotMap::new(),
+            dedup: Dedup::Always,
+        }
+    }
+
+    /// Creates an [`EffectCell`] in change-detection mode: effects only run when a
+    /// mutation actually changes the stored value, compared against a cached
+    /// snapshot via [`PartialEq`]. Idempotent writes do not re-trigger effects.
+    ///
+    /// Use [`force`](Self::force) to run effects regardless of the comparison.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::cell::Cell;
+    /// use std::rc::Rc;
+    /// use effect_cell::EffectCell;
+    ///
+    /// let fires = Rc::new(Cell::new(0));
+    /// let mut cell = EffectCell::new_deduped(1);
+    /// let f = fires.clone();
+    /// cell.bind(move |_| f.set(f.get() + 1));
+    ///
+    /// cell.update(2); // changed 1 -> 2, effect fires
+    /// cell.update(2); // idempotent write, effect is skipped
+    /// assert_eq!(fires.get(), 1);
+    ///
+    /// cell.force(); // runs effects regardless of the comparison
+    /// assert_eq!(fires.get(), 2);
+    /// ```
+    pub fn new_deduped(data: T) -> Self
+    where
+        T: Clone + PartialEq + 'static,
+    {
+        let mut last: Option<T> = Some(data.clone());
+        let dedup = Dedup::Custom(Box::new(move |current: &T| {
+            let changed = last.as_ref() != Some(current);
+            if changed {
+                last = Some(current.clone());
+            }
+            changed
+        }));
+        Self {
+            data,
+            effects: SlotMap::new(),
+            dedup,
+        }
+    }
+
+    /// Creates an [`EffectCell`] in change-detection mode backed by a 64-bit
+    /// fingerprint rather than a cloned snapshot, for large `T` where cloning is
+    /// costly. The fingerprint is computed with [`DefaultHasher`].
+    ///
+    /// Because distinct values can (vanishingly rarely) hash to the same 64-bit
+    /// fingerprint, a genuine change that collides with the previous value's
+    /// fingerprint will be treated as "unchanged" and skip its effects.
+    pub fn new_fingerprinted(data: T) -> Self
+    where
+        T: Hash + 'static,
+    {
+        let mut last: Option<u64> = Some(fingerprint(&data));
+        let dedup = Dedup::Custom(Box::new(move |current: &T| {
+            let fp = fingerprint(current);
+            let changed = last != Some(fp);
+            if changed {
+                last = Some(fp);
+            }
+            changed
+        }));
+        Self {
+            data,
+            effects: SlotMap::new(),
+            dedup,
         }
     }
 
@@ -36,15 +264,82 @@ impl<T> EffectCell<T> {
         self.data
     }
 
-    /// Binds a new effect callback to the [`EffectCell`]
-    pub fn bind<F: FnMut(&T) + 'static>(&mut self, effect: F) {
-        self.effects.push(Box::new(effect));
+    /// Returns a reference to the stored data.
+    pub fn get(&self) -> &T {
+        &self.data
+    }
+
+    /// Binds a new effect callback to the [`EffectCell`], returning a key that
+    /// can later be passed to [`unbind`](Self::unbind) or [`rebind`](Self::rebind).
+    pub fn bind<F: FnMut(&T) + 'static>(&mut self, effect: F) -> EffectKey {
+        self.effects.insert(Box::new(effect))
+    }
+
+    /// Removes the effect identified by `key`, returning `true` if it was still
+    /// bound. A stale or already-removed key returns `false`.
+    ///
+    /// # Examples
+    ///
+    /// Recycling a freed slot preserves insertion order, and a stale key can
+    /// never address the recycled slot (ABA safety):
+    ///
+    /// ```
+    /// use std::cell::RefCell;
+    /// use std::rc::Rc;
+    /// use effect_cell::EffectCell;
+    ///
+    /// let log = Rc::new(RefCell::new(Vec::new()));
+    /// let mut cell = EffectCell::new(());
+    /// let l = log.clone();
+    /// cell.bind(move |_| l.borrow_mut().push(1));
+    /// let l = log.clone();
+    /// let k2 = cell.bind(move |_| l.borrow_mut().push(2));
+    /// let l = log.clone();
+    /// cell.bind(move |_| l.borrow_mut().push(3));
+    ///
+    /// // Remove effect 2, then bind effect 4, which recycles effect 2's slot.
+    /// assert!(cell.unbind(k2));
+    /// let l = log.clone();
+    /// cell.bind(move |_| l.borrow_mut().push(4));
+    ///
+    /// cell.call();
+    /// assert_eq!(*log.borrow(), vec![1, 3, 4]);
+    ///
+    /// // The stale key is rejected rather than removing the recycled effect.
+    /// assert!(!cell.unbind(k2));
+    /// ```
+    pub fn unbind(&mut self, key: EffectKey) -> bool {
+        self.effects.remove(key)
+    }
+
+    /// Replaces the effect identified by `key` with `new_effect`, returning
+    /// `true` if the key still referred to a bound effect.
+    pub fn rebind<F: FnMut(&T) + 'static>(&mut self, key: EffectKey, new_effect: F) -> bool {
+        self.effects.replace(key, Box::new(new_effect))
     }
 
     /// Runs all effects with the current data.
     pub fn call(&mut self) {
-        for f in &mut self.effects {
-            f(&self.data);
+        self.effects.call(&self.data);
+    }
+
+    /// Runs all effects with the current data, unconditionally, regardless of the
+    /// change-detection mode. The dedup baseline is refreshed so the next
+    /// mutation is compared against the current value.
+    pub fn force(&mut self) {
+        self.refresh_baseline();
+        self.call();
+    }
+
+    /// Runs effects subject to the change-detection mode: always in [`Dedup::Always`],
+    /// otherwise only when the detection closure reports a change.
+    fn run_effects(&mut self) {
+        let run = match &mut self.dedup {
+            Dedup::Always => true,
+            Dedup::Custom(f) => f(&self.data),
+        };
+        if run {
+            self.call();
         }
     }
 
@@ -68,7 +363,7 @@ impl<T> EffectCell<T> {
     /// ```
     pub fn update(&mut self, new: T) {
         self.data = new;
-        self.call();
+        self.run_effects();
     }
 
     /// Updates the inner value using the provided function and runs the effects with the new value.
@@ -86,17 +381,34 @@ impl<T> EffectCell<T> {
     /// ```
     pub fn update_lambda<F: FnMut(&mut T) + 'static>(&mut self, mut lambda: F) {
         lambda(&mut self.data);
-        self.call();
+        self.run_effects();
     }
 
-    /// Updates the inner value without running any effects
+    /// Updates the inner value without running any effects.
+    ///
+    /// In a change-detection cell (see [`new_deduped`](Self::new_deduped)) the
+    /// dedup baseline is still refreshed so a later [`update`](Self::update) to
+    /// the same value is correctly seen as unchanged.
     pub fn set(&mut self, new: T) {
         self.data = new;
+        self.refresh_baseline();
     }
 
-    /// Updates the inner value using the provided function without running any effects
+    /// Updates the inner value using the provided function without running any
+    /// effects.
+    ///
+    /// As with [`set`](Self::set), the dedup baseline is refreshed.
     pub fn set_lambda<F: FnMut(&T) + 'static>(&mut self, mut lambda: F) {
         lambda(&mut self.data);
+        self.refresh_baseline();
+    }
+
+    /// Re-syncs the change-detection baseline to the current value without
+    /// running effects. A no-op for cells in [`Dedup::Always`] mode.
+    fn refresh_baseline(&mut self) {
+        if let Dedup::Custom(f) = &mut self.dedup {
+            let _ = f(&self.data);
+        }
     }
 }
 
@@ -172,8 +484,19 @@ impl<T: Debug> Debug for EffectCell<T> {
 /// ```
 pub struct OrderedEffectCell<T> {
     data: T,
-    prior_effects: Vec<Box<dyn FnMut(&T)>>,
-    post_effects: Vec<Box<dyn FnMut(&T)>>,
+    prior_effects: SlotMap<T>,
+    post_effects: SlotMap<T>,
+}
+
+/// An opaque handle to an effect bound to an [`OrderedEffectCell`].
+///
+/// Like [`EffectKey`] but also records which of the `Prior`/`Post` stores the
+/// effect lives in, so it can be routed back to the correct store when
+/// unbinding or rebinding.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct OrderedEffectKey {
+    order: EffectOrder,
+    key: EffectKey,
 }
 
 impl<T> OrderedEffectCell<T> {
@@ -181,8 +504,8 @@ impl<T> OrderedEffectCell<T> {
     pub fn new(data: T) -> Self {
         Self {
             data,
-            prior_effects: Vec::new(),
-            post_effects: Vec::new(),
+            prior_effects: SlotMap::new(),
+            post_effects: SlotMap::new(),
         }
     }
 
@@ -191,31 +514,57 @@ impl<T> OrderedEffectCell<T> {
         self.data
     }
 
-    /// Binds a new effect callback to [`OrderedEffectCell`] based on the given [`EffectOrder`]
-    pub fn bind<F: FnMut(&T) + 'static>(&mut self, ord: EffectOrder, effect: F) {
-        match ord {
-            EffectOrder::Prior => {
-                self.prior_effects.push(Box::new(effect));
-            }
-            EffectOrder::Post => {
-                self.post_effects.push(Box::new(effect));
-            }
+    /// Binds a new effect callback to [`OrderedEffectCell`] at the given phase,
+    /// returning a key that can later be passed to [`unbind`](Self::unbind) or
+    /// [`rebind`](Self::rebind).
+    ///
+    /// `phase` may be an [`EffectOrder`] (priority `0`) or an [`EffectPhase`]
+    /// carrying an explicit priority; within a side effects run in ascending
+    /// priority order.
+    pub fn bind<P: Into<EffectPhase>, F: FnMut(&T) + 'static>(
+        &mut self,
+        phase: P,
+        effect: F,
+    ) -> OrderedEffectKey {
+        let phase = phase.into();
+        let effect: Effect<T> = Box::new(effect);
+        let key = match phase.order {
+            EffectOrder::Prior => self.prior_effects.insert_with_priority(effect, phase.priority),
+            EffectOrder::Post => self.post_effects.insert_with_priority(effect, phase.priority),
+        };
+        OrderedEffectKey {
+            order: phase.order,
+            key,
+        }
+    }
+
+    /// Removes the effect identified by `key`, returning `true` if it was still
+    /// bound. A stale or already-removed key returns `false`.
+    pub fn unbind(&mut self, key: OrderedEffectKey) -> bool {
+        match key.order {
+            EffectOrder::Prior => self.prior_effects.remove(key.key),
+            EffectOrder::Post => self.post_effects.remove(key.key),
+        }
+    }
+
+    /// Replaces the effect identified by `key` with `new_effect`, returning
+    /// `true` if the key still referred to a bound effect.
+    pub fn rebind<F: FnMut(&T) + 'static>(
+        &mut self,
+        key: OrderedEffectKey,
+        new_effect: F,
+    ) -> bool {
+        match key.order {
+            EffectOrder::Prior => self.prior_effects.replace(key.key, Box::new(new_effect)),
+            EffectOrder::Post => self.post_effects.replace(key.key, Box::new(new_effect)),
         }
     }
 
-    /// Runs all effects of the given [`EffectOrder`]
+    /// Runs all effects of the given [`EffectOrder`] side in ascending priority order
     pub fn call(&mut self, ord: EffectOrder) {
         match ord {
-            EffectOrder::Prior => {
-                for f in &mut self.prior_effects {
-                    f(&self.data);
-                }
-            }
-            EffectOrder::Post => {
-                for f in &mut self.post_effects {
-                    f(&self.data);
-                }
-            }
+            EffectOrder::Prior => self.prior_effects.call_sorted(&self.data),
+            EffectOrder::Post => self.post_effects.call_sorted(&self.data),
         }
     }
 
@@ -354,7 +703,7 @@ macro_rules! impl_pass_op {
         {
             fn $fn_name(&mut self, other: T) {
                 self.data $op other;
-                self.call()
+                self.run_effects()
             }
         }
     }
@@ -409,10 +758,438 @@ impl_struct_pass!(EffectCell<T>);
 impl_struct_pass_ord!(OrderedEffectCell<T>);
 
 /// Represents whether an effect should be called before or after data is updated
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum EffectOrder {
     /// Represents the ordering of an effect that should be called before data is updated
     Prior,
     /// Represents the ordering of an effect the should be called after data is updated
     Post,
 }
+
+/// A side (`Prior`/`Post`) relative to a mutation together with an `i32`
+/// priority within that side.
+///
+/// Within a side, effects run in ascending priority order. The bare
+/// [`EffectOrder`] variants convert to priority `0`, so `EffectOrder::Prior` and
+/// `EffectPhase::prior(0)` are equivalent; this lets callers interleave, e.g., a
+/// validation effect at a lower priority ahead of a logging effect at a higher
+/// one, both before the write.
+///
+/// # Examples
+///
+/// ```
+/// use effect_cell::OrderedEffectCell;
+/// use effect_cell::EffectPhase;
+///
+/// let mut cell = OrderedEffectCell::new(0);
+/// cell.bind(EffectPhase::prior(-1), |data| { println!("validate {data}"); });
+/// cell.bind(EffectPhase::prior(1), |data| { println!("log {data}"); });
+/// cell.update(2);
+///
+/// // Prints the following:
+/// // validate 0
+/// // log 0
+/// ```
+///
+/// Priorities order effects within each side, and the `Prior` side always runs
+/// before the `Post` side:
+///
+/// ```
+/// use std::cell::RefCell;
+/// use std::rc::Rc;
+/// use effect_cell::{EffectOrder, EffectPhase, OrderedEffectCell};
+///
+/// let log = Rc::new(RefCell::new(Vec::new()));
+/// let mut cell = OrderedEffectCell::new(0);
+/// // Bind out of order; execution order is governed by side then priority.
+/// let l = log.clone();
+/// cell.bind(EffectPhase::post(5), move |_| l.borrow_mut().push("post+5"));
+/// let l = log.clone();
+/// cell.bind(EffectPhase::prior(2), move |_| l.borrow_mut().push("prior+2"));
+/// let l = log.clone();
+/// cell.bind(EffectOrder::Prior, move |_| l.borrow_mut().push("prior+0"));
+/// let l = log.clone();
+/// cell.bind(EffectPhase::prior(-3), move |_| l.borrow_mut().push("prior-3"));
+/// let l = log.clone();
+/// cell.bind(EffectPhase::post(-1), move |_| l.borrow_mut().push("post-1"));
+///
+/// cell.update(1);
+/// assert_eq!(
+///     *log.borrow(),
+///     vec!["prior-3", "prior+0", "prior+2", "post-1", "post+5"],
+/// );
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct EffectPhase {
+    /// Which side of the mutation the effect runs on.
+    pub order: EffectOrder,
+    /// The effect's priority within its side; lower runs first.
+    pub priority: i32,
+}
+
+impl EffectPhase {
+    /// A [`Prior`](EffectOrder::Prior) phase with the given priority.
+    pub fn prior(priority: i32) -> Self {
+        Self {
+            order: EffectOrder::Prior,
+            priority,
+        }
+    }
+
+    /// A [`Post`](EffectOrder::Post) phase with the given priority.
+    pub fn post(priority: i32) -> Self {
+        Self {
+            order: EffectOrder::Post,
+            priority,
+        }
+    }
+}
+
+impl From<EffectOrder> for EffectPhase {
+    /// Maps the bare `Prior`/`Post` aliases to priority `0`.
+    fn from(order: EffectOrder) -> Self {
+        Self { order, priority: 0 }
+    }
+}
+
+/// Identifies a node within a [`ReactiveGraph`].
+///
+/// Returned by [`ReactiveGraph::add_source`] and [`ReactiveGraph::bind_derived`]
+/// and used to address a node when reading or updating it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct NodeId(usize);
+
+/// The outcome of a propagation pass over a [`ReactiveGraph`].
+///
+/// Acyclic graphs always [`Converged`](Propagation::Converged) after a single
+/// pass. Cyclic graphs are iterated to a fixpoint and may instead report
+/// [`Exhausted`](Propagation::Exhausted) if the caller-supplied iteration cap is
+/// reached before the values stop changing.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Propagation {
+    /// The graph settled; holds the number of passes that were required.
+    Converged(usize),
+    /// The graph did not settle within the supplied cap; holds that cap.
+    Exhausted(usize),
+}
+
+/// A derived node's recompute closure, as registered by [`ReactiveGraph::bind_derived`].
+type Recompute<T> = Box<dyn FnMut(&[&dyn Any]) -> T>;
+
+struct Node<T> {
+    cell: EffectCell<T>,
+    deps: Vec<usize>,
+    recompute: Option<Recompute<T>>,
+}
+
+/// A small reactive runtime layered on top of [`EffectCell`].
+///
+/// Nodes may declare dependencies on other nodes; updating a source node
+/// automatically recomputes every node that transitively reads it. Each derived
+/// node stores a recompute closure `FnMut(&[&dyn Any]) -> T` that receives its
+/// declared dependencies (in the order they were given to
+/// [`bind_derived`](ReactiveGraph::bind_derived)) and produces the node's new
+/// value.
+///
+/// Propagation is glitch-free: a cached topological order — recomputed whenever
+/// the graph is mutated — guarantees that within a pass no node is recomputed
+/// before every node it reads has settled. Cyclic graphs are iterated to a
+/// fixpoint, comparing values via the [`PartialEq`] impl on [`EffectCell`], up to
+/// a caller-supplied iteration cap.
+///
+/// # Examples
+///
+/// ```
+/// use effect_cell::ReactiveGraph;
+///
+/// let mut graph = ReactiveGraph::new();
+/// let a = graph.add_source(2i64);
+/// let doubled = graph.bind_derived(vec![a], |deps| {
+///     let a = deps[0].downcast_ref::<i64>().unwrap();
+///     a * 2
+/// });
+///
+/// graph.update(a, 5, 16);
+/// assert_eq!(*graph.get(doubled), 10);
+/// ```
+pub struct ReactiveGraph<T> {
+    nodes: Vec<Node<T>>,
+    dependents: Vec<Vec<usize>>,
+    dirty: Vec<bool>,
+    topo: Vec<usize>,
+    topo_stale: bool,
+}
+
+impl<T> Default for ReactiveGraph<T>
+where
+    T: PartialEq + 'static,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> ReactiveGraph<T>
+where
+    T: PartialEq + 'static,
+{
+    /// Create a new, empty [`ReactiveGraph`].
+    pub fn new() -> Self {
+        Self {
+            nodes: Vec::new(),
+            dependents: Vec::new(),
+            dirty: Vec::new(),
+            topo: Vec::new(),
+            topo_stale: false,
+        }
+    }
+
+    /// Adds a source node holding `value` and returns its [`NodeId`].
+    ///
+    /// Source nodes have no recompute closure; their value only changes through
+    /// [`update`](ReactiveGraph::update) or [`update_lambda`](ReactiveGraph::update_lambda).
+    pub fn add_source(&mut self, value: T) -> NodeId {
+        self.push_node(EffectCell::new(value), Vec::new(), None)
+    }
+
+    /// Registers a derived node that recomputes from `deps` and returns its [`NodeId`].
+    ///
+    /// The closure receives the current values of `deps`, in order, as
+    /// `&[&dyn Any]` and must return the node's value. The node is computed once
+    /// immediately so that the graph is consistent on return.
+    pub fn bind_derived<F: FnMut(&[&dyn Any]) -> T + 'static>(
+        &mut self,
+        deps: Vec<NodeId>,
+        f: F,
+    ) -> NodeId {
+        let deps: Vec<usize> = deps.into_iter().map(|NodeId(i)| i).collect();
+        let mut recompute: Recompute<T> = Box::new(f);
+        // Compute the initial value so the graph is consistent on return.
+        let initial = self.recompute_value(&deps, &mut *recompute);
+        self.push_node(EffectCell::new(initial), deps, Some(recompute))
+    }
+
+    /// Redefines the node at `id` to recompute from `deps`, replacing any
+    /// existing dependencies and recompute closure, and returns the node.
+    ///
+    /// Unlike [`bind_derived`](ReactiveGraph::bind_derived) — whose `deps` can
+    /// only name already-created, lower-indexed nodes — `deps` here may name
+    /// nodes created after `id` (including `id`'s own downstream nodes), so this
+    /// is how dependency cycles are introduced. The node is recomputed once
+    /// immediately; drive the resulting cycle to a fixpoint with
+    /// [`update`](ReactiveGraph::update) or [`propagate`](ReactiveGraph::propagate)
+    /// and a suitable `max_passes`.
+    pub fn rebind_derived<F: FnMut(&[&dyn Any]) -> T + 'static>(
+        &mut self,
+        id: NodeId,
+        deps: Vec<NodeId>,
+        f: F,
+    ) -> NodeId {
+        let deps: Vec<usize> = deps.into_iter().map(|NodeId(i)| i).collect();
+        // Drop the old dependency edges, then install the new ones.
+        let old = std::mem::take(&mut self.nodes[id.0].deps);
+        for dep in old {
+            self.dependents[dep].retain(|&d| d != id.0);
+        }
+        for &dep in &deps {
+            self.dependents[dep].push(id.0);
+        }
+        let mut recompute: Recompute<T> = Box::new(f);
+        let value = self.recompute_value(&deps, &mut *recompute);
+        self.nodes[id.0].deps = deps;
+        self.nodes[id.0].recompute = Some(recompute);
+        self.nodes[id.0].cell.update(value);
+        self.topo_stale = true;
+        id
+    }
+
+    /// Binds a new effect callback to the node identified by `id`.
+    ///
+    /// The effect fires whenever the node is updated or rebound directly, and
+    /// whenever a recompute changes its value; a recompute that leaves the value
+    /// unchanged does not fire it. It uses the same `FnMut(&T)` convention as
+    /// [`EffectCell::bind`].
+    pub fn bind<F: FnMut(&T) + 'static>(&mut self, id: NodeId, effect: F) {
+        self.nodes[id.0].cell.bind(effect);
+    }
+
+    /// Returns a reference to the value stored at `id`.
+    pub fn get(&self, id: NodeId) -> &T {
+        self.nodes[id.0].cell.get()
+    }
+
+    /// Replaces the value at `id` and propagates the change to its dependents.
+    ///
+    /// See [`propagate`](ReactiveGraph::propagate) for the meaning of `max_passes`.
+    pub fn update(&mut self, id: NodeId, new: T, max_passes: usize) -> Propagation {
+        self.nodes[id.0].cell.update(new);
+        self.mark_dependents_dirty(id.0);
+        self.propagate(max_passes)
+    }
+
+    /// Mutates the value at `id` in place and propagates the change to its dependents.
+    ///
+    /// See [`propagate`](ReactiveGraph::propagate) for the meaning of `max_passes`.
+    pub fn update_lambda<F: FnMut(&mut T) + 'static>(
+        &mut self,
+        id: NodeId,
+        lambda: F,
+        max_passes: usize,
+    ) -> Propagation {
+        self.nodes[id.0].cell.update_lambda(lambda);
+        self.mark_dependents_dirty(id.0);
+        self.propagate(max_passes)
+    }
+
+    fn push_node(
+        &mut self,
+        cell: EffectCell<T>,
+        deps: Vec<usize>,
+        recompute: Option<Recompute<T>>,
+    ) -> NodeId {
+        let index = self.nodes.len();
+        for &dep in &deps {
+            self.dependents[dep].push(index);
+        }
+        self.nodes.push(Node {
+            cell,
+            deps,
+            recompute,
+        });
+        self.dependents.push(Vec::new());
+        self.dirty.push(false);
+        self.topo_stale = true;
+        NodeId(index)
+    }
+
+    /// Recomputes the cached topological order by post-order DFS over the
+    /// dependency edges, so that every node appears after all the nodes it reads.
+    fn rebuild_topo(&mut self) {
+        let n = self.nodes.len();
+        let mut order = Vec::with_capacity(n);
+        let mut visited = vec![false; n];
+        // Iterative post-order DFS; `order` ends up with dependencies before the
+        // nodes that read them.
+        for start in 0..n {
+            if visited[start] {
+                continue;
+            }
+            let mut stack = vec![(start, 0usize)];
+            while let Some((node, next)) = stack.pop() {
+                if next == 0 {
+                    visited[node] = true;
+                }
+                if next < self.nodes[node].deps.len() {
+                    let dep = self.nodes[node].deps[next];
+                    stack.push((node, next + 1));
+                    if !visited[dep] {
+                        stack.push((dep, 0));
+                    }
+                } else {
+                    order.push(node);
+                }
+            }
+        }
+        self.topo = order;
+        self.topo_stale = false;
+    }
+
+    fn mark_dependents_dirty(&mut self, index: usize) {
+        let mut stack = self.dependents[index].clone();
+        while let Some(node) = stack.pop() {
+            if !self.dirty[node] {
+                self.dirty[node] = true;
+                stack.extend_from_slice(&self.dependents[node]);
+            }
+        }
+    }
+
+    fn recompute_value(
+        &self,
+        deps: &[usize],
+        recompute: &mut dyn FnMut(&[&dyn Any]) -> T,
+    ) -> T {
+        let refs: Vec<&dyn Any> = deps
+            .iter()
+            .map(|&d| self.nodes[d].cell.get() as &dyn Any)
+            .collect();
+        recompute(&refs)
+    }
+
+    /// Walks the cached topological order, recomputing dirty nodes in a
+    /// glitch-free order and iterating to a fixpoint for cyclic graphs.
+    ///
+    /// Returns [`Propagation::Converged`] with the number of passes taken, or
+    /// [`Propagation::Exhausted`] if `max_passes` is reached before the graph
+    /// settles. A node is considered settled when a recompute leaves its value
+    /// unchanged according to the [`PartialEq`] impl on [`EffectCell`].
+    ///
+    /// # Examples
+    ///
+    /// An acyclic graph settles in a single pass; an ever-growing cycle exhausts
+    /// the cap, while a saturating cycle eventually converges:
+    ///
+    /// ```
+    /// use effect_cell::{Propagation, ReactiveGraph};
+    ///
+    /// // Diamond DAG: a -> {b, c} -> d. One pass settles it.
+    /// let mut dag = ReactiveGraph::new();
+    /// let a = dag.add_source(1i64);
+    /// let b = dag.bind_derived(vec![a], |d| d[0].downcast_ref::<i64>().unwrap() + 1);
+    /// let c = dag.bind_derived(vec![a], |d| d[0].downcast_ref::<i64>().unwrap() + 2);
+    /// dag.bind_derived(vec![b, c], |d| {
+    ///     d[0].downcast_ref::<i64>().unwrap() + d[1].downcast_ref::<i64>().unwrap()
+    /// });
+    /// assert_eq!(dag.update(a, 10, 16), Propagation::Converged(1));
+    ///
+    /// // Cycle whose values keep growing never settles within the cap.
+    /// let mut cyclic = ReactiveGraph::new();
+    /// let x = cyclic.add_source(0i64);
+    /// let y = cyclic.add_source(0i64);
+    /// cyclic.rebind_derived(x, vec![y], |d| d[0].downcast_ref::<i64>().unwrap() + 1);
+    /// cyclic.rebind_derived(y, vec![x], |d| d[0].downcast_ref::<i64>().unwrap() + 1);
+    /// assert_eq!(cyclic.update(x, 1, 8), Propagation::Exhausted(8));
+    ///
+    /// // Clamping the same cycle lets it reach a fixpoint.
+    /// cyclic.rebind_derived(x, vec![y], |d| (*d[0].downcast_ref::<i64>().unwrap()).min(5));
+    /// cyclic.rebind_derived(y, vec![x], |d| (d[0].downcast_ref::<i64>().unwrap() + 1).min(5));
+    /// assert!(matches!(cyclic.update(x, 0, 50), Propagation::Converged(_)));
+    /// assert_eq!(*cyclic.get(y), 5);
+    /// ```
+    pub fn propagate(&mut self, max_passes: usize) -> Propagation {
+        if self.topo_stale {
+            self.rebuild_topo();
+        }
+        for pass in 1..=max_passes {
+            for i in 0..self.topo.len() {
+                let node = self.topo[i];
+                if !self.dirty[node] {
+                    continue;
+                }
+                self.dirty[node] = false;
+                // Source nodes carry no recompute closure and never change here.
+                let Some(mut recompute) = self.nodes[node].recompute.take() else {
+                    continue;
+                };
+                let deps = std::mem::take(&mut self.nodes[node].deps);
+                let new = self.recompute_value(&deps, &mut *recompute);
+                self.nodes[node].deps = deps;
+                self.nodes[node].recompute = Some(recompute);
+                if self.nodes[node].cell != new {
+                    self.nodes[node].cell.update(new);
+                    for dep in self.dependents[node].clone() {
+                        self.dirty[dep] = true;
+                    }
+                }
+            }
+            // In topological order a single pass settles every node that only has
+            // forward edges, so a DAG leaves no node dirty after pass 1. A node
+            // left dirty can only be a back-edge dependent (a cycle); iterate
+            // until the dirty set drains or the cap is hit.
+            if !self.dirty.iter().any(|&d| d) {
+                return Propagation::Converged(pass);
+            }
+        }
+        Propagation::Exhausted(max_passes)
+    }
+}